@@ -21,6 +21,7 @@ use clap::Parser;
 use std::fmt::Write;
 use std::fs::File;
 use std::io::BufReader;
+use std::io::IsTerminal;
 use std::io::prelude::*;
 
 
@@ -28,20 +29,148 @@ const STD_BYTES_PER_LINE: usize = 16;
 const STD_BYTES_PER_SEGMENT: usize = 8;
 const WOZ_BYTES_PER_LINE: usize = 8;
 const WOZ_BYTES_PER_SEGMENT: usize = 0;  // 0 = "no segmenting"
+const READ_BLOCK_SIZE: usize = 64 * 1024;
+
+
+/// Numeric radix used to render each byte
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum NumBase {
+    Hex,
+    Octal,
+    Binary,
+    Decimal,
+}
+
+impl NumBase {
+    // Number of digits needed to show a byte (0-255) in this base
+    fn digits(&self) -> usize {
+        match self {
+            NumBase::Hex => 2,
+            NumBase::Octal => 3,
+            NumBase::Binary => 8,
+            NumBase::Decimal => 3,
+        }
+    }
+}
+
+impl std::fmt::Display for NumBase {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let s = match self {
+            NumBase::Hex => "hex",
+            NumBase::Octal => "octal",
+            NumBase::Binary => "binary",
+            NumBase::Decimal => "decimal",
+        };
+        write!(f, "{s}")
+    }
+}
+
+
+/// When to colorize output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl std::fmt::Display for ColorMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let s = match self {
+            ColorMode::Auto => "auto",
+            ColorMode::Always => "always",
+            ColorMode::Never => "never",
+        };
+        write!(f, "{s}")
+    }
+}
+
+// What kind of byte this is, for color classification
+enum ByteCategory {
+    Null,
+    Printable,
+    Whitespace,
+    High,
+}
+
+fn categorize(byte: u8) -> ByteCategory {
+    match byte {
+        0x00 => ByteCategory::Null,
+        0x20..=0x7E => ByteCategory::Printable,
+        0x80..=0xFF => ByteCategory::High,
+        _ => ByteCategory::Whitespace,  // other control characters
+    }
+}
+
+// ANSI color to use for a byte's category
+fn ansi_color(byte: u8) -> &'static str {
+    match categorize(byte) {
+        ByteCategory::Null => "\x1b[90m",       // bright black
+        ByteCategory::Printable => "\x1b[32m",  // green
+        ByteCategory::Whitespace => "\x1b[33m", // yellow
+        ByteCategory::High => "\x1b[31m",       // red
+    }
+}
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+// Resolve --color auto/always/never against NO_COLOR and whether stdout is a TTY
+fn resolve_use_color(mode: ColorMode) -> bool {
+    match mode {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal(),
+    }
+}
+
+
+/// Source language for --array output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ArrayLang {
+    C,
+    Rust,
+    Asm,
+}
+
+impl std::fmt::Display for ArrayLang {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let s = match self {
+            ArrayLang::C => "c",
+            ArrayLang::Rust => "rust",
+            ArrayLang::Asm => "asm",
+        };
+        write!(f, "{s}")
+    }
+}
 
 
 /// Display a file in hexadecimal and ASCII
 #[derive(Parser)]
 #[command(version)]
 struct Cli {
-    /// File to display
-    file: std::path::PathBuf,
+    /// File to display (reads from stdin if omitted)
+    file: Option<std::path::PathBuf>,
     /// Override default zero origin (pass 0 to get origin from first two bytes of <FILE> in little-endian order)
     #[arg(short, long, value_name="ADDRESS")]
     origin: Option<String>,
     /// Display in wozmon format
     #[arg(short, long)]
     woz: bool,
+    /// Numeric base to display byte values in
+    #[arg(short, long, value_enum, default_value_t = NumBase::Hex)]
+    format: NumBase,
+    /// Emit Intel HEX records instead of a human-readable dump
+    #[arg(long)]
+    ihex: bool,
+    /// Colorize output by byte type (null, printable, whitespace/control, high)
+    #[arg(long, value_enum, default_value_t = ColorMode::Auto)]
+    color: ColorMode,
+    /// Emit file contents as an embeddable source array instead of a dump
+    #[arg(short, long)]
+    array: bool,
+    /// Source language to use for --array output
+    #[arg(long = "lang", value_enum, default_value_t = ArrayLang::C)]
+    array_lang: ArrayLang,
 }
 
 #[derive(Debug)]
@@ -50,6 +179,8 @@ struct LineFormat {
     bytes_per_segment: usize,
     left_padding: usize,
     show_ascii: bool,
+    base: NumBase,
+    color: bool,
 }
 
 
@@ -63,13 +194,34 @@ fn parse_hex(s: &str) -> Result<usize, std::num::ParseIntError> {
 }
 
 
+// Write a single byte, in the line's chosen numeric base, straight into line
+fn write_cell(line: &mut String, byte: u8, base: NumBase, color: bool) {
+    if color {
+        write!(line, " {}", ansi_color(byte)).unwrap();
+    } else {
+        line.push(' ');
+    }
+    match base {
+        NumBase::Hex => write!(line, "{byte:02X}").unwrap(),
+        NumBase::Octal => write!(line, "{byte:03o}").unwrap(),
+        NumBase::Binary => write!(line, "{byte:08b}").unwrap(),
+        NumBase::Decimal => write!(line, "{byte:03}").unwrap(),
+    }
+    if color {
+        line.push_str(ANSI_RESET);
+    }
+}
+
+
 // Print one line of bytes (buffer may be shorter than full BYTES_PER_LINE)
 fn print_buffer(bytes: &Vec<u8>, line_addr: usize, fmt: &LineFormat) {
     let mut line = String::with_capacity(80);
+    // Width of one rendered cell, including its leading space
+    let cell_width = fmt.base.digits() + 1;
 
     // Start each line with the address of first byte (masked to 16 bits)
     write!(line, "{:04X}:", line_addr & 0xFFFF).unwrap();
-    
+
     // Cycle through each position for the hex part of the line
     let mut i = 0;  // Current index into bytes vector
     for pos in 0..fmt.bytes_per_line {
@@ -79,18 +231,20 @@ fn print_buffer(bytes: &Vec<u8>, line_addr: usize, fmt: &LineFormat) {
         };
         // Pad (left or right) if needed
         if (pos < fmt.left_padding) || (fmt.show_ascii && i >= bytes.len()) {
-            line.push_str("   ");
+            line.push_str(&" ".repeat(cell_width));
         } else {
-            // Otherwise, show byte in hex if we have any bytes left
+            // Otherwise, show byte in the chosen base if we have any bytes left
             if i < bytes.len() {
-                write!(line, " {:02X}", bytes[i]).unwrap();
+                write_cell(&mut line, bytes[i], fmt.base, fmt.color);
                 i += 1;
             }
         }
     }
 
     if fmt.show_ascii {
-        // A bit of space between hex and characters...
+        // A bit of space between hex and characters. Each skipped left-padding
+        // slot maps to exactly one ASCII character regardless of cell width,
+        // since the hex region's own width is already handled by cell_width.
         let mut pad_remaining = fmt.left_padding + 2;
         while pad_remaining > 0 {
             line.push(' ');
@@ -98,11 +252,12 @@ fn print_buffer(bytes: &Vec<u8>, line_addr: usize, fmt: &LineFormat) {
         }
         // Show characters
         for byte in bytes {
-            line.push(if *byte >= 0x20_u8 && *byte < 0x7F {
-                          *byte as char
-                      } else {
-                          '.'
-                      });
+            let ch = if *byte >= 0x20_u8 && *byte < 0x7F { *byte as char } else { '.' };
+            if fmt.color {
+                write!(line, "{}{ch}{ANSI_RESET}", ansi_color(*byte)).unwrap();
+            } else {
+                line.push(ch);
+            }
         }
     }
 
@@ -111,14 +266,101 @@ fn print_buffer(bytes: &Vec<u8>, line_addr: usize, fmt: &LineFormat) {
 
 
 
+// Two's complement of the sum of a record's bytes, mod 256
+fn ihex_checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |sum, &b| sum.wrapping_add(b)).wrapping_neg()
+}
+
+
+// Print one Intel HEX record: length, address, type, data, checksum
+fn print_ihex_record(record_type: u8, address: u16, data: &[u8]) {
+    let mut record = vec![data.len() as u8, (address >> 8) as u8, (address & 0xFF) as u8, record_type];
+    record.extend_from_slice(data);
+    record.push(ihex_checksum(&record));
+    let hex_string: String = record.iter().map(|b| format!("{b:02X}")).collect();
+    println!(":{hex_string}");
+}
+
+
+// Print an extended linear address record carrying the upper 16 bits of addr
+fn print_ihex_ext_addr(addr: usize) {
+    let upper = ((addr >> 16) & 0xFFFF) as u16;
+    print_ihex_record(0x04, 0x0000, &[(upper >> 8) as u8, (upper & 0xFF) as u8]);
+}
+
+
+// Print one line of bytes as an Intel HEX data record (up to 16 bytes)
+fn print_ihex_buffer(bytes: &[u8], line_addr: usize) {
+    print_ihex_record(0x00, (line_addr & 0xFFFF) as u16, bytes);
+}
+
+
+// Print one line's worth of bytes, either as a normal dump line or, in ihex
+// mode, as an Intel HEX data record (preceded by an extended linear address
+// record whenever the line has crossed into a new 64K bank)
+fn print_line(ihex: bool, bytes: &Vec<u8>, line_addr: usize, fmt: &LineFormat, ihex_ext_addr: &mut Option<usize>) {
+    if ihex {
+        if *ihex_ext_addr != Some(line_addr >> 16) {
+            print_ihex_ext_addr(line_addr);
+            *ihex_ext_addr = Some(line_addr >> 16);
+        }
+        print_ihex_buffer(bytes, line_addr);
+    } else {
+        print_buffer(bytes, line_addr, fmt);
+    }
+}
+
+
+// Render one line's worth of bytes as a "0xXX, 0xXX, ..." row (no trailing space)
+fn c_style_row(chunk: &[u8]) -> String {
+    let row: String = chunk.iter().map(|b| format!("0x{b:02X}, ")).collect();
+    row.trim_end().to_string()
+}
+
+
+// Print the file's bytes as a ready-to-paste source array/definition
+fn print_array(bytes: &[u8], lang: ArrayLang, bytes_per_line: usize) {
+    let len = bytes.len();
+    match lang {
+        ArrayLang::C => {
+            println!("unsigned char data[{len}] = {{");
+            for chunk in bytes.chunks(bytes_per_line) {
+                println!("    {}", c_style_row(chunk));
+            }
+            println!("}};");
+            println!("// {len} bytes total");
+        }
+        ArrayLang::Rust => {
+            println!("pub const DATA: [u8; {len}] = [");
+            for chunk in bytes.chunks(bytes_per_line) {
+                println!("    {}", c_style_row(chunk));
+            }
+            println!("];");
+            println!("// {len} bytes total");
+        }
+        ArrayLang::Asm => {
+            println!("DATA");
+            for chunk in bytes.chunks(bytes_per_line) {
+                let row: Vec<String> = chunk.iter().map(|b| format!("${b:02X}")).collect();
+                println!("    .byte {}", row.join(","));
+            }
+            println!("; {len} bytes total");
+        }
+    }
+}
+
+
 fn main() -> std::io::Result<()> {
     
     let args = Cli::parse();
     let mut addr: usize = 0;
 
-    // Get file and open a buffered reader on it
-    let file = File::open(args.file)?;  // TODO: Better error message here?
-    let mut buf_reader = BufReader::new(file);
+    // Get file (or stdin, if none given) and open a buffered reader on it
+    let source: Box<dyn Read> = match args.file {
+        Some(path) => Box::new(File::open(path)?),  // TODO: Better error message here?
+        None => Box::new(std::io::stdin().lock()),
+    };
+    let mut buf_reader = BufReader::new(source);
     
     // Update starting address if user specified an origin
     if let Some(origin) = args.origin.as_deref() {
@@ -134,42 +376,72 @@ fn main() -> std::io::Result<()> {
         }
     }
 
+    // Array mode just needs the whole file and a different formatter
+    if args.array {
+        let mut bytes = Vec::new();
+        buf_reader.read_to_end(&mut bytes)?;  // TODO: Better error
+        print_array(&bytes, args.array_lang, STD_BYTES_PER_LINE);
+        return Ok(());
+    }
+
     // Initialize formatting parameters
+    let use_color = resolve_use_color(args.color);
     let mut fmt = if args.woz {
         LineFormat {bytes_per_line: WOZ_BYTES_PER_LINE,
                     bytes_per_segment: WOZ_BYTES_PER_SEGMENT,
                     left_padding: 0,
                     show_ascii: false,
+                    base: args.format,
+                    color: use_color,
                    }
     } else {
         LineFormat {bytes_per_line: STD_BYTES_PER_LINE,
                     bytes_per_segment: STD_BYTES_PER_SEGMENT,
                     left_padding: addr % STD_BYTES_PER_LINE,
                     show_ascii: true,
+                    base: args.format,
+                    color: use_color,
                    }
     };
     
     // Set up buffers, etc.
-    let mut byte_buffer = [0];
-    let mut line_buffer = Vec::with_capacity(fmt.bytes_per_line);
-    let mut bytes_read: usize;
+    let mut block_buffer = vec![0_u8; READ_BLOCK_SIZE];
+    // Holds bytes read but not yet long enough to fill a full line, carried
+    // over across block reads
+    let mut line_buffer: Vec<u8> = Vec::with_capacity(fmt.bytes_per_line);
     let mut line_addr = addr;
-    
-    // MAIN LOOP: Read byte-by-byte and print when we have enough for a line
+    // Upper 16 bits of the address last announced via an ihex extended
+    // linear address record; starts at 0 since that's the implicit bank
+    // before any record has been emitted
+    let mut ihex_ext_addr: Option<usize> = Some(0);
+
+    // MAIN LOOP: Fill a big block in one read, then slice it into lines
     loop {
-        bytes_read = buf_reader.read(&mut byte_buffer)?;  // TODO: Better error
+        let bytes_read = buf_reader.read(&mut block_buffer)?;  // TODO: Better error
         if bytes_read == 0 {
             // End of file... print out line buffer if it's got anything in it
             if !line_buffer.is_empty() {
-                print_buffer(&line_buffer, line_addr, &fmt);
+                print_line(args.ihex, &line_buffer, line_addr, &fmt, &mut ihex_ext_addr);
+            }
+            if args.ihex {
+                println!(":00000001FF");
             }
             break;
-        } else {
-            line_buffer.push(byte_buffer[0]);
-            addr += 1;
+        }
+
+        let mut block = &block_buffer[..bytes_read];
+        while !block.is_empty() {
+            // Take only as many bytes as needed to complete the current line
+            // (the very first line may be short, if origin isn't line-aligned)
+            let needed = fmt.bytes_per_line - (addr % fmt.bytes_per_line);
+            let take = needed.min(block.len());
+            line_buffer.extend_from_slice(&block[..take]);
+            block = &block[take..];
+            addr += take;
+
             if addr % fmt.bytes_per_line == 0 {
                 // Print buffer
-                print_buffer(&line_buffer, line_addr, &fmt);
+                print_line(args.ihex, &line_buffer, line_addr, &fmt, &mut ihex_ext_addr);
                 // Clear the buffer and any initial padding
                 line_buffer.clear();
                 fmt.left_padding = 0;